@@ -9,11 +9,286 @@ use crate::circuits::{CommonGateConfig, Limb};
 
 use std::marker::PhantomData;
 
+use crate::value_for_assign;
+use halo2_proofs::poly::Rotation;
 use halo2_proofs::{
     circuit::Region,
-    plonk::{ConstraintSystem, Error},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed, Selector},
 };
 
+/// Common surface for a Poseidon-based chip, so a circuit can be written
+/// once against `permute`/`hash` and instantiated with whichever
+/// `Spec<F, T, RATE>` parameter set it needs (e.g. the wide sponge spec for
+/// hashing WASM memory, or the 2:1 compression spec for Merkle nodes).
+pub trait PoseidonInstructions<F: FieldExt, const T: usize, const RATE: usize> {
+    fn permute(
+        &mut self,
+        region: &mut Region<F>,
+        offset: &mut usize,
+        values: &[Limb<F>; RATE],
+        reset: &Limb<F>,
+    ) -> Result<Limb<F>, Error>;
+
+    fn hash(
+        &mut self,
+        region: &mut Region<F>,
+        offset: &mut usize,
+        message: &[Limb<F>],
+    ) -> Result<Limb<F>, Error>;
+}
+
+impl<F: FieldExt, const T: usize, const RATE: usize> PoseidonInstructions<F, T, RATE>
+    for PoseidonChip<F, T, RATE>
+{
+    fn permute(
+        &mut self,
+        region: &mut Region<F>,
+        offset: &mut usize,
+        values: &[Limb<F>; RATE],
+        reset: &Limb<F>,
+    ) -> Result<Limb<F>, Error> {
+        self.get_permute_result(region, offset, values, reset)
+    }
+
+    fn hash(
+        &mut self,
+        region: &mut Region<F>,
+        offset: &mut usize,
+        message: &[Limb<F>],
+    ) -> Result<Limb<F>, Error> {
+        PoseidonChip::hash(self, region, offset, message)
+    }
+}
+
+/// The standard 2:1 compression spec (`T=3, RATE=2`) used for Merkle node
+/// hashing, as opposed to the wide `POSEIDON_HASHER_SPEC` (`T=9, RATE=8`)
+/// sponge used for hashing arbitrary-length WASM memory.
+pub type Poseidon2To1Chip<F> = PoseidonChip<F, 3, 2>;
+
+/// Dedicated columns and custom gate for the two-partial-rounds-per-row
+/// layout, ported from the Pow5 packing strategy in the halo2 Poseidon
+/// gadget. Round A's sbox output (`state[0]^5 + rc_a`) is witnessed through
+/// `mid` so that the sparse mix following it, and round B's sbox on the
+/// mixed state, can be closed into the next row's state by a single
+/// bounded-degree custom gate instead of the several rows the
+/// generic `sbox_part`/`apply_sparse_mds` path needs per round.
+///
+/// `col_hat_a`/`col_hat_b` are sized `T` for const-generic convenience;
+/// index `0` is unused (the sparse MDS "col_hat" vector only has `T - 1`
+/// entries, one per non-zeroth state element).
+#[derive(Clone, Debug)]
+pub struct DensePartialRoundConfig<const T: usize> {
+    state: [Column<Advice>; T],
+    mid: Column<Advice>,
+    rc_a: Column<Fixed>,
+    rc_b: Column<Fixed>,
+    row_a: [Column<Fixed>; T],
+    col_hat_a: [Column<Fixed>; T],
+    row_b: [Column<Fixed>; T],
+    col_hat_b: [Column<Fixed>; T],
+    s_dense: Selector,
+}
+
+impl<const T: usize> DensePartialRoundConfig<T> {
+    pub fn configure<F: FieldExt>(cs: &mut ConstraintSystem<F>) -> Self {
+        let state = [0u32; T].map(|_| cs.advice_column());
+        for column in state.iter() {
+            cs.enable_equality(*column);
+        }
+        let mid = cs.advice_column();
+        cs.enable_equality(mid);
+
+        let rc_a = cs.fixed_column();
+        let rc_b = cs.fixed_column();
+        let row_a = [0u32; T].map(|_| cs.fixed_column());
+        let col_hat_a = [0u32; T].map(|_| cs.fixed_column());
+        let row_b = [0u32; T].map(|_| cs.fixed_column());
+        let col_hat_b = [0u32; T].map(|_| cs.fixed_column());
+        let s_dense = cs.selector();
+
+        cs.create_gate("poseidon dense partial round pair", |meta| {
+            let s = meta.query_selector(s_dense);
+            let state_cur: Vec<_> = state
+                .iter()
+                .map(|c| meta.query_advice(*c, Rotation::cur()))
+                .collect();
+            let state_next: Vec<_> = state
+                .iter()
+                .map(|c| meta.query_advice(*c, Rotation::next()))
+                .collect();
+            let mid_cur = meta.query_advice(mid, Rotation::cur());
+            let rc_a_q = meta.query_fixed(rc_a, Rotation::cur());
+            let rc_b_q = meta.query_fixed(rc_b, Rotation::cur());
+            let row_a_q: Vec<_> = row_a
+                .iter()
+                .map(|c| meta.query_fixed(*c, Rotation::cur()))
+                .collect();
+            let col_hat_a_q: Vec<_> = col_hat_a
+                .iter()
+                .map(|c| meta.query_fixed(*c, Rotation::cur()))
+                .collect();
+            let row_b_q: Vec<_> = row_b
+                .iter()
+                .map(|c| meta.query_fixed(*c, Rotation::cur()))
+                .collect();
+            let col_hat_b_q: Vec<_> = col_hat_b
+                .iter()
+                .map(|c| meta.query_fixed(*c, Rotation::cur()))
+                .collect();
+
+            // Round A sbox: witnessed `mid` must equal state_cur[0]^5 + rc_a.
+            let x = state_cur[0].clone();
+            let x2 = x.clone() * x.clone();
+            let x4 = x2.clone() * x2.clone();
+            let sbox_a_check = x4 * x + rc_a_q - mid_cur.clone();
+
+            // Round A sparse mix, expressed in terms of the witnessed `mid`:
+            // mix_a[0] is the full sparse-row dot product, mix_a[i] (i>0) is
+            // the rank-1 update `col_hat_a[i] * mid + state_cur[i]`.
+            let mut mix_a = vec![row_a_q[0].clone() * mid_cur.clone(); T];
+            for i in 1..T {
+                mix_a[0] = mix_a[0].clone() + row_a_q[i].clone() * state_cur[i].clone();
+            }
+            for i in 1..T {
+                mix_a[i] = col_hat_a_q[i].clone() * mid_cur.clone() + state_cur[i].clone();
+            }
+
+            // Round B sbox on the mixed state, inlined (no extra witness
+            // needed: mix_a[0] is already a degree-1 combination of cells).
+            let y = mix_a[0].clone();
+            let y2 = y.clone() * y.clone();
+            let y4 = y2.clone() * y2.clone();
+            let sbox_b = y4 * y + rc_b_q;
+
+            // Round B sparse mix, closed directly into the next row's state.
+            let mut new_state0 = row_b_q[0].clone() * sbox_b.clone();
+            for i in 1..T {
+                new_state0 = new_state0 + row_b_q[i].clone() * mix_a[i].clone();
+            }
+
+            let mut constraints = vec![
+                s.clone() * sbox_a_check,
+                s.clone() * (state_next[0].clone() - new_state0),
+            ];
+            for i in 1..T {
+                let new_state_i = col_hat_b_q[i].clone() * sbox_b.clone() + mix_a[i].clone();
+                constraints.push(s.clone() * (state_next[i].clone() - new_state_i));
+            }
+            constraints
+        });
+
+        DensePartialRoundConfig {
+            state,
+            mid,
+            rc_a,
+            rc_b,
+            row_a,
+            col_hat_a,
+            row_b,
+            col_hat_b,
+            s_dense,
+        }
+    }
+
+    /// Assign one packed row: absorb round A's (constant, sparse row,
+    /// sparse col_hat), then round B's, copying `state` in from whatever
+    /// columns the caller's limbs live in and returning the new state bound
+    /// to this config's columns on the following row.
+    #[allow(clippy::too_many_arguments)]
+    pub fn assign_pair<F: FieldExt>(
+        &self,
+        region: &mut Region<F>,
+        offset: &mut usize,
+        state: &[Limb<F>; T],
+        constant_a: F,
+        row_a: &[F],
+        col_hat_a: &[F],
+        constant_b: F,
+        row_b: &[F],
+        col_hat_b: &[F],
+    ) -> Result<[Limb<F>; T], Error> {
+        assert_eq!(row_a.len(), T);
+        assert_eq!(row_b.len(), T);
+        assert_eq!(col_hat_a.len(), T - 1);
+        assert_eq!(col_hat_b.len(), T - 1);
+
+        self.s_dense.enable(region, *offset)?;
+
+        for (column, limb) in self.state.iter().zip(state.iter()) {
+            let cell = region.assign_advice(
+                || "dense partial round: copy state in",
+                *column,
+                *offset,
+                || value_for_assign!(limb.value),
+            )?;
+            region.constrain_equal(limb.cell.as_ref().unwrap().cell(), cell.cell())?;
+        }
+
+        region.assign_fixed(|| "rc_a", self.rc_a, *offset, || value_for_assign!(constant_a))?;
+        region.assign_fixed(|| "rc_b", self.rc_b, *offset, || value_for_assign!(constant_b))?;
+        for (column, value) in self.row_a.iter().zip(row_a.iter()) {
+            region.assign_fixed(|| "row_a", *column, *offset, || value_for_assign!(*value))?;
+        }
+        for (column, value) in self.row_b.iter().zip(row_b.iter()) {
+            region.assign_fixed(|| "row_b", *column, *offset, || value_for_assign!(*value))?;
+        }
+        for (i, value) in col_hat_a.iter().enumerate() {
+            region.assign_fixed(
+                || "col_hat_a",
+                self.col_hat_a[i + 1],
+                *offset,
+                || value_for_assign!(*value),
+            )?;
+        }
+        for (i, value) in col_hat_b.iter().enumerate() {
+            region.assign_fixed(
+                || "col_hat_b",
+                self.col_hat_b[i + 1],
+                *offset,
+                || value_for_assign!(*value),
+            )?;
+        }
+
+        let s0 = state[0].value;
+        let mid_value = s0 * s0 * s0 * s0 * s0 + constant_a;
+        region.assign_advice(|| "mid", self.mid, *offset, || value_for_assign!(mid_value))?;
+
+        let mut mix_a = vec![F::zero(); T];
+        mix_a[0] = row_a[0] * mid_value;
+        for i in 1..T {
+            mix_a[0] = mix_a[0] + row_a[i] * state[i].value;
+        }
+        for i in 1..T {
+            mix_a[i] = col_hat_a[i - 1] * mid_value + state[i].value;
+        }
+
+        let m0 = mix_a[0];
+        let sbox_b_value = m0 * m0 * m0 * m0 * m0 + constant_b;
+
+        let mut new_state_values = vec![F::zero(); T];
+        new_state_values[0] = row_b[0] * sbox_b_value;
+        for i in 1..T {
+            new_state_values[0] = new_state_values[0] + row_b[i] * mix_a[i];
+        }
+        for i in 1..T {
+            new_state_values[i] = col_hat_b[i - 1] * sbox_b_value + mix_a[i];
+        }
+
+        *offset += 1;
+        let mut new_state = Vec::with_capacity(T);
+        for (column, value) in self.state.iter().zip(new_state_values.iter()) {
+            let cell = region.assign_advice(
+                || "dense partial round: state out",
+                *column,
+                *offset,
+                || value_for_assign!(*value),
+            )?;
+            new_state.push(Limb::new(Some(cell), *value));
+        }
+        Ok(new_state.try_into().unwrap())
+    }
+}
+
 pub struct PoseidonState<F: FieldExt, const T: usize> {
     state: [Limb<F>; T],
     default: [Limb<F>; T],
@@ -25,11 +300,31 @@ pub struct PoseidonChip<F: FieldExt, const T: usize, const RATE: usize> {
     pub spec: Spec<F, T, RATE>,
     poseidon_state: PoseidonState<F, T>,
     round: u64,
+    dense: Option<DensePartialRoundConfig<T>>,
     _marker: PhantomData<F>,
 }
 
 impl<F: FieldExt, const T: usize, const RATE: usize> PoseidonChip<F, T, RATE> {
     pub fn construct(config: CommonGateConfig, spec: Spec<F, T, RATE>) -> Self {
+        Self::new(config, spec, None)
+    }
+
+    /// Like [`Self::construct`], but opts into the two-partial-rounds-per-row
+    /// layout built by [`Self::configure_dense`]. Existing callers keep using
+    /// `construct`/`configure` and get the generic layout, unchanged.
+    pub fn construct_dense(
+        config: CommonGateConfig,
+        dense: DensePartialRoundConfig<T>,
+        spec: Spec<F, T, RATE>,
+    ) -> Self {
+        Self::new(config, spec, Some(dense))
+    }
+
+    fn new(
+        config: CommonGateConfig,
+        spec: Spec<F, T, RATE>,
+        dense: Option<DensePartialRoundConfig<T>>,
+    ) -> Self {
         let state = [0u32; T].map(|_| Limb::new(None, F::zero()));
         let state = PoseidonState {
             default: state.clone(),
@@ -42,6 +337,7 @@ impl<F: FieldExt, const T: usize, const RATE: usize> PoseidonChip<F, T, RATE> {
             config,
             spec,
             poseidon_state: state,
+            dense,
             _marker: PhantomData,
         }
     }
@@ -59,6 +355,17 @@ impl<F: FieldExt, const T: usize, const RATE: usize> PoseidonChip<F, T, RATE> {
         CommonGateConfig::configure(cs, &())
     }
 
+    /// Like [`Self::configure`], but also builds the columns and custom gate
+    /// for the two-partial-rounds-per-row layout (see
+    /// [`DensePartialRoundConfig`]), for callers that then construct their
+    /// chip with [`Self::construct_dense`].
+    pub fn configure_dense(cs: &mut ConstraintSystem<F>) -> (CommonGateConfig, DensePartialRoundConfig<T>) {
+        (
+            CommonGateConfig::configure(cs, &()),
+            DensePartialRoundConfig::configure(cs),
+        )
+    }
+
     pub(crate) fn get_permute_result(
         &mut self,
         region: &mut Region<F>,
@@ -84,8 +391,14 @@ impl<F: FieldExt, const T: usize, const RATE: usize> PoseidonChip<F, T, RATE> {
             )?);
         }
         self.poseidon_state.state = new_state.try_into().unwrap();
-        self.poseidon_state
-            .permute(&self.config, &self.spec, region, offset, values)?;
+        self.poseidon_state.permute(
+            &self.config,
+            self.dense.as_ref(),
+            &self.spec,
+            region,
+            offset,
+            values,
+        )?;
         Ok(self.poseidon_state.state[1].clone())
     }
 
@@ -105,6 +418,117 @@ impl<F: FieldExt, const T: usize, const RATE: usize> PoseidonChip<F, T, RATE> {
         )?;
         Ok(())
     }
+
+    /// Absorb a message of arbitrary length and squeeze a single output limb,
+    /// mirroring the `ConstantLength` domain of the halo2 Poseidon gadget: the
+    /// message is split into `RATE`-sized chunks, the running state carries
+    /// across chunks (only the first chunk resets to the default state), and
+    /// the final chunk is padded with the message length followed by zeros
+    /// before being absorbed.
+    pub fn hash(
+        &mut self,
+        region: &mut Region<F>,
+        offset: &mut usize,
+        message: &[Limb<F>],
+    ) -> Result<Limb<F>, Error> {
+        let mut chunks = message.chunks_exact(RATE);
+        let mut blocks = chunks
+            .by_ref()
+            .map(|chunk| chunk.to_vec().try_into().unwrap())
+            .collect::<Vec<[Limb<F>; RATE]>>();
+
+        let zero = self.config.assign_constant(region, &mut (), offset, &F::zero())?;
+        let marker =
+            self.config
+                .assign_constant(region, &mut (), offset, &F::from(message.len() as u64))?;
+        let mut last = chunks.remainder().to_vec();
+        last.push(marker);
+        while last.len() < RATE {
+            last.push(zero.clone());
+        }
+        blocks.push(last.try_into().unwrap());
+
+        let mut result = None;
+        for (i, block) in blocks.iter().enumerate() {
+            let reset_value = if i == 0 { F::one() } else { F::zero() };
+            let reset = self
+                .config
+                .assign_constant(region, &mut (), offset, &reset_value)?;
+            result = Some(self.get_permute_result(region, offset, block, &reset)?);
+        }
+        Ok(result.unwrap())
+    }
+
+    /// Absorb `values` into the running transcript state, chunking into
+    /// `RATE`-sized blocks with the last one padded by zeros only (unlike
+    /// `hash`, no length marker is appended), resetting
+    /// to the default state only on the very first absorb performed on this
+    /// chip, and chaining afterwards via `self.round`.
+    fn absorb(
+        &mut self,
+        region: &mut Region<F>,
+        offset: &mut usize,
+        values: &[Limb<F>],
+    ) -> Result<Limb<F>, Error> {
+        let zero = self.config.assign_constant(region, &mut (), offset, &F::zero())?;
+
+        let mut result = None;
+        for chunk in values.chunks(RATE) {
+            let mut block = chunk.to_vec();
+            while block.len() < RATE {
+                block.push(zero.clone());
+            }
+
+            let reset_value = if self.round == 0 { F::one() } else { F::zero() };
+            let reset = self
+                .config
+                .assign_constant(region, &mut (), offset, &reset_value)?;
+            result = Some(self.get_permute_result(
+                region,
+                offset,
+                &block.try_into().unwrap(),
+                &reset,
+            )?);
+            self.round += 1;
+        }
+        Ok(result.unwrap())
+    }
+
+    /// Fiat-Shamir: absorb a scalar tagged with `PREFIX_SCALAR`.
+    pub fn common_scalar(
+        &mut self,
+        region: &mut Region<F>,
+        offset: &mut usize,
+        scalar: &Limb<F>,
+    ) -> Result<(), Error> {
+        let prefix = self.poseidon_state.prefix[2].clone();
+        self.absorb(region, offset, &[prefix, scalar.clone()])?;
+        Ok(())
+    }
+
+    /// Fiat-Shamir: absorb an elliptic curve point's limbs tagged with `PREFIX_POINT`.
+    pub fn common_point(
+        &mut self,
+        region: &mut Region<F>,
+        offset: &mut usize,
+        point: &[Limb<F>],
+    ) -> Result<(), Error> {
+        let prefix = self.poseidon_state.prefix[1].clone();
+        let mut values = vec![prefix];
+        values.extend_from_slice(point);
+        self.absorb(region, offset, &values)?;
+        Ok(())
+    }
+
+    /// Fiat-Shamir: squeeze a challenge tagged with `PREFIX_CHALLENGE`.
+    pub fn squeeze_challenge(
+        &mut self,
+        region: &mut Region<F>,
+        offset: &mut usize,
+    ) -> Result<Limb<F>, Error> {
+        let prefix = self.poseidon_state.prefix[0].clone();
+        self.absorb(region, offset, &[prefix])
+    }
 }
 
 impl<F: FieldExt, const T: usize> PoseidonState<F, T> {
@@ -244,6 +668,7 @@ impl<F: FieldExt, const T: usize> PoseidonState<F, T> {
     pub fn permute<const RATE: usize>(
         &mut self,
         config: &CommonGateConfig,
+        dense: Option<&DensePartialRoundConfig<T>>,
         spec: &Spec<F, T, RATE>,
         region: &mut Region<F>,
         offset: &mut usize,
@@ -266,9 +691,48 @@ impl<F: FieldExt, const T: usize> PoseidonState<F, T> {
 
         let sparse_matrices = &spec.mds_matrices().sparse_matrices();
         let constants = &spec.constants().partial();
-        for (constant, sparse_mds) in constants.iter().zip(sparse_matrices.iter()) {
-            self.sbox_part(config, region, offset, constant)?;
-            self.apply_sparse_mds(config, region, offset, sparse_mds)?;
+        match dense {
+            // Two-partial-rounds-per-row layout: pack rounds pairwise into
+            // a single `DensePartialRoundConfig` gate, falling back to the
+            // generic per-round path for a final unpaired round.
+            Some(dense) => {
+                let mut pairs = constants.iter().zip(sparse_matrices.iter());
+                loop {
+                    let first = pairs.next();
+                    let Some((constant_a, sparse_a)) = first else {
+                        break;
+                    };
+                    match pairs.next() {
+                        Some((constant_b, sparse_b)) => {
+                            let row_a: Vec<F> = sparse_a.row().iter().copied().collect();
+                            let row_b: Vec<F> = sparse_b.row().iter().copied().collect();
+                            let col_hat_a: Vec<F> = sparse_a.col_hat().iter().copied().collect();
+                            let col_hat_b: Vec<F> = sparse_b.col_hat().iter().copied().collect();
+                            self.state = dense.assign_pair(
+                                region,
+                                offset,
+                                &self.state,
+                                *constant_a,
+                                &row_a,
+                                &col_hat_a,
+                                *constant_b,
+                                &row_b,
+                                &col_hat_b,
+                            )?;
+                        }
+                        None => {
+                            self.sbox_part(config, region, offset, constant_a)?;
+                            self.apply_sparse_mds(config, region, offset, sparse_a)?;
+                        }
+                    }
+                }
+            }
+            None => {
+                for (constant, sparse_mds) in constants.iter().zip(sparse_matrices.iter()) {
+                    self.sbox_part(config, region, offset, constant)?;
+                    self.apply_sparse_mds(config, region, offset, sparse_mds)?;
+                }
+            }
         }
 
         let constants = &spec.constants().end();
@@ -384,7 +848,9 @@ impl<F: FieldExt, const T: usize> PoseidonState<F, T> {
 #[cfg(test)]
 mod tests {
     use crate::circuits::CommonGateConfig;
-    use crate::host::poseidon::POSEIDON_HASHER_SPEC;
+    use crate::host::poseidon::{
+        PREFIX_CHALLENGE, PREFIX_POINT, PREFIX_SCALAR, POSEIDON_HASHER, POSEIDON_HASHER_SPEC,
+    };
     use crate::value_for_assign;
     use halo2_proofs::dev::MockProver;
     use halo2_proofs::pairing::bn256::Fr;
@@ -573,4 +1039,383 @@ mod tests {
         let prover = MockProver::run(16, &test_circuit, vec![]).unwrap();
         assert_eq!(prover.verify(), Ok(()));
     }
+
+    #[derive(Clone, Debug, Default)]
+    struct DenseTestCircuit {
+        inputs: Vec<Fr>,
+        result: Fr,
+    }
+
+    #[derive(Clone, Debug)]
+    struct DenseTestConfig {
+        poseidonconfig: CommonGateConfig,
+        denseconfig: super::DensePartialRoundConfig<9>,
+        helperconfig: HelperChipConfig,
+    }
+
+    impl Circuit<Fr> for DenseTestCircuit {
+        type Config = DenseTestConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+            let (poseidonconfig, denseconfig) = PoseidonChip::<Fr, 9, 8>::configure_dense(meta);
+            Self::Config {
+                poseidonconfig,
+                denseconfig,
+                helperconfig: HelperChip::configure(meta),
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fr>,
+        ) -> Result<(), Error> {
+            let mut poseidonchip = PoseidonChip::<Fr, 9, 8>::construct_dense(
+                config.clone().poseidonconfig,
+                config.clone().denseconfig,
+                POSEIDON_HASHER_SPEC.clone(),
+            );
+            let helperchip = HelperChip::new(config.clone().helperconfig);
+            layouter.assign_region(
+                || "assign poseidon dense test",
+                |mut region| {
+                    let mut offset = 0;
+                    let result =
+                        helperchip.assign_result(&mut region, &mut offset, &self.result)?;
+                    let inputs =
+                        helperchip.assign_inputs(&mut region, &mut offset, &self.inputs.clone())?;
+                    let reset = helperchip.assign_reset(&mut region, &mut offset, true)?;
+                    offset = 0;
+                    poseidonchip.poseidon_state.initialize(
+                        &config.poseidonconfig,
+                        &mut region,
+                        &mut offset,
+                    )?;
+                    poseidonchip.assign_permute(
+                        &mut region,
+                        &mut offset,
+                        &inputs.try_into().unwrap(),
+                        &reset,
+                        &result,
+                    )?;
+                    Ok(())
+                },
+            )?;
+            Ok(())
+        }
+    }
+
+    /// The two-partial-rounds-per-row layout must compute exactly the same
+    /// permutation as the generic one-round-at-a-time layout: this checks the
+    /// dense layout against the same off-circuit reference output that
+    /// `test_poseidon_circuit_00` checks the generic layout against.
+    #[test]
+    fn test_poseidon_circuit_dense_parity() {
+        let mut hasher = crate::host::poseidon::POSEIDON_HASHER.clone();
+        let result = hasher.squeeze();
+        let inputs = vec![
+            Fr::one(),
+            Fr::zero(),
+            Fr::zero(),
+            Fr::zero(),
+            Fr::zero(),
+            Fr::zero(),
+            Fr::zero(),
+            Fr::zero(),
+        ];
+        let test_circuit = DenseTestCircuit { inputs, result };
+        let prover = MockProver::run(16, &test_circuit, vec![]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+    }
+
+    #[derive(Clone, Debug, Default)]
+    struct TranscriptTestCircuit {
+        scalar: Fr,
+        point: Vec<Fr>,
+        expected: Fr,
+    }
+
+    #[derive(Clone, Debug)]
+    struct TranscriptTestConfig {
+        poseidonconfig: CommonGateConfig,
+        helperconfig: HelperChipConfig,
+    }
+
+    impl Circuit<Fr> for TranscriptTestCircuit {
+        type Config = TranscriptTestConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+            Self::Config {
+                poseidonconfig: PoseidonChip::<Fr, 9, 8>::configure(meta),
+                helperconfig: HelperChip::configure(meta),
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fr>,
+        ) -> Result<(), Error> {
+            let mut poseidonchip = PoseidonChip::<Fr, 9, 8>::construct(
+                config.clone().poseidonconfig,
+                POSEIDON_HASHER_SPEC.clone(),
+            );
+            let helperchip = HelperChip::new(config.clone().helperconfig);
+            layouter.assign_region(
+                || "assign transcript test",
+                |mut region| {
+                    let mut offset = 0;
+                    let expected =
+                        helperchip.assign_result(&mut region, &mut offset, &self.expected)?;
+                    let scalar = helperchip.assign_result(&mut region, &mut offset, &self.scalar)?;
+                    let point =
+                        helperchip.assign_inputs(&mut region, &mut offset, &self.point.clone())?;
+
+                    offset = 0;
+                    poseidonchip.initialize(&config.poseidonconfig, &mut region, &mut offset)?;
+                    poseidonchip.common_scalar(&mut region, &mut offset, &scalar)?;
+                    poseidonchip.common_point(&mut region, &mut offset, &point)?;
+                    let challenge = poseidonchip.squeeze_challenge(&mut region, &mut offset)?;
+
+                    assert!(challenge.value == expected.value);
+                    region.constrain_equal(
+                        expected.cell.as_ref().unwrap().cell(),
+                        challenge.cell.as_ref().unwrap().cell(),
+                    )?;
+                    Ok(())
+                },
+            )?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_poseidon_transcript_chain() {
+        let mut hasher = POSEIDON_HASHER.clone();
+        let scalar = Fr::from(7u64);
+        let point = vec![Fr::from(11u64), Fr::from(13u64)];
+
+        hasher.update(&[
+            Fr::from(PREFIX_SCALAR),
+            scalar,
+            Fr::zero(),
+            Fr::zero(),
+            Fr::zero(),
+            Fr::zero(),
+            Fr::zero(),
+            Fr::zero(),
+        ]);
+        hasher.update(&[
+            Fr::from(PREFIX_POINT),
+            point[0],
+            point[1],
+            Fr::zero(),
+            Fr::zero(),
+            Fr::zero(),
+            Fr::zero(),
+            Fr::zero(),
+        ]);
+        hasher.update(&[
+            Fr::from(PREFIX_CHALLENGE),
+            Fr::zero(),
+            Fr::zero(),
+            Fr::zero(),
+            Fr::zero(),
+            Fr::zero(),
+            Fr::zero(),
+            Fr::zero(),
+        ]);
+        let expected = hasher.squeeze();
+
+        let test_circuit = TranscriptTestCircuit {
+            scalar,
+            point,
+            expected,
+        };
+        let prover = MockProver::run(16, &test_circuit, vec![]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+    }
+
+    #[derive(Clone, Debug, Default)]
+    struct TestCircuit2To1 {
+        left: Fr,
+        right: Fr,
+        result: Fr,
+    }
+
+    #[derive(Clone, Debug)]
+    struct TestConfig2To1 {
+        poseidonconfig: CommonGateConfig,
+        helperconfig: HelperChipConfig,
+    }
+
+    impl Circuit<Fr> for TestCircuit2To1 {
+        type Config = TestConfig2To1;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+            Self::Config {
+                poseidonconfig: super::Poseidon2To1Chip::<Fr>::configure(meta),
+                helperconfig: HelperChip::configure(meta),
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fr>,
+        ) -> Result<(), Error> {
+            let mut poseidonchip = super::Poseidon2To1Chip::<Fr>::construct(
+                config.clone().poseidonconfig,
+                crate::host::poseidon::POSEIDON_HASHER_SPEC_2TO1.clone(),
+            );
+            let helperchip = HelperChip::new(config.clone().helperconfig);
+            layouter.assign_region(
+                || "assign poseidon 2:1 test",
+                |mut region| {
+                    let mut offset = 0;
+                    let result =
+                        helperchip.assign_result(&mut region, &mut offset, &self.result)?;
+                    let inputs = helperchip.assign_inputs(
+                        &mut region,
+                        &mut offset,
+                        &vec![self.left, self.right],
+                    )?;
+                    let reset = helperchip.assign_reset(&mut region, &mut offset, true)?;
+                    offset = 0;
+                    poseidonchip.poseidon_state.initialize(
+                        &config.poseidonconfig,
+                        &mut region,
+                        &mut offset,
+                    )?;
+                    poseidonchip.assign_permute(
+                        &mut region,
+                        &mut offset,
+                        &inputs.try_into().unwrap(),
+                        &reset,
+                        &result,
+                    )?;
+                    Ok(())
+                },
+            )?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_poseidon_circuit_2to1() {
+        let mut hasher = crate::host::poseidon::POSEIDON_HASHER_2TO1.clone();
+        let left = Fr::one();
+        let right = Fr::zero();
+        hasher.update(&[left, right]);
+        let result = hasher.squeeze();
+        let test_circuit = TestCircuit2To1 {
+            left,
+            right,
+            result,
+        };
+        let prover = MockProver::run(16, &test_circuit, vec![]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+    }
+
+    #[derive(Clone, Debug, Default)]
+    struct HashTestCircuit {
+        message: Vec<Fr>,
+        expected: Fr,
+    }
+
+    #[derive(Clone, Debug)]
+    struct HashTestConfig {
+        poseidonconfig: CommonGateConfig,
+        helperconfig: HelperChipConfig,
+    }
+
+    impl Circuit<Fr> for HashTestCircuit {
+        type Config = HashTestConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+            Self::Config {
+                poseidonconfig: PoseidonChip::<Fr, 9, 8>::configure(meta),
+                helperconfig: HelperChip::configure(meta),
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fr>,
+        ) -> Result<(), Error> {
+            let mut poseidonchip = PoseidonChip::<Fr, 9, 8>::construct(
+                config.clone().poseidonconfig,
+                POSEIDON_HASHER_SPEC.clone(),
+            );
+            let helperchip = HelperChip::new(config.clone().helperconfig);
+            layouter.assign_region(
+                || "assign poseidon hash test",
+                |mut region| {
+                    let mut offset = 0;
+                    let expected =
+                        helperchip.assign_result(&mut region, &mut offset, &self.expected)?;
+                    let message =
+                        helperchip.assign_inputs(&mut region, &mut offset, &self.message)?;
+                    offset = 0;
+                    poseidonchip.poseidon_state.initialize(
+                        &config.poseidonconfig,
+                        &mut region,
+                        &mut offset,
+                    )?;
+                    let result = poseidonchip.hash(&mut region, &mut offset, &message)?;
+                    region.constrain_equal(
+                        expected.cell.as_ref().unwrap().cell(),
+                        result.cell.as_ref().unwrap().cell(),
+                    )?;
+                    Ok(())
+                },
+            )?;
+            Ok(())
+        }
+    }
+
+    /// `message` here is 10 elements long, so `hash` splits it into a full
+    /// first `RATE`-sized (8) chunk plus a second, shorter chunk padded with
+    /// the length marker and zeros — the only way to exercise the carry-over
+    /// from the first permute's state into the second (and the fact that the
+    /// reset flag is only ever set on the very first block).
+    #[test]
+    fn test_poseidon_hash_multi_chunk() {
+        let message: Vec<Fr> = (1..=10u64).map(Fr::from).collect();
+
+        let mut hasher = POSEIDON_HASHER.clone();
+        hasher.update(&message[0..8]);
+        let mut last_block = message[8..10].to_vec();
+        last_block.push(Fr::from(message.len() as u64));
+        while last_block.len() < 8 {
+            last_block.push(Fr::zero());
+        }
+        hasher.update(&last_block);
+        let expected = hasher.squeeze();
+
+        let test_circuit = HashTestCircuit { message, expected };
+        let prover = MockProver::run(16, &test_circuit, vec![]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+    }
 }